@@ -1,18 +1,70 @@
-use std::{fmt, str::FromStr};
+use std::{
+    cell::RefCell,
+    fmt,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use hex::FromHex;
+use rand::Rng;
 
-/// Neon ID is a 128-bit random ID.
+mod error;
+pub use error::{ApiError, ErrorBody};
+
+pub mod repository;
+
+pub mod version;
+
+#[cfg(feature = "cbor")]
+pub use cbor::IdAsBytes;
+
+/// Neon ID is a 128-bit ID.
 /// Used to represent various identifiers. Provides handy utility methods and impls.
 ///
+/// Construct one with [`Id::generate`] for a uniformly random id, or [`Id::generate_sortable`]
+/// for an id that sorts by creation time (the first 6 bytes are a big-endian
+/// Unix-millisecond timestamp, the rest is random) -- handy when ids are generated by
+/// different, uncoordinated callers but still need to come out in creation order.
+///
 /// NOTE: It (de)serializes as an array of hex bytes, so the string representation would look
 /// like `[173,80,132,115,129,226,72,254,170,201,135,108,199,26,228,24]`.
 ///
 /// Use `#[serde_as(as = "DisplayFromStr")]` to (de)serialize it as hex string instead: `ad50847381e248feaac9876cc71ae418`.
+/// With the `cbor` feature enabled, `#[serde_as(as = "IdAsBytes")]` (de)serializes it as a
+/// 16-byte CBOR byte string instead, which is both more compact and what [`Id::encode`] /
+/// [`Id::put_to_buf`] use for binary protocols and on-disk records.
 /// Check the `serde_with::serde_as` documentation for options for more complex types.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Id([u8; 16]);
 
+// Thread-local state for `Id::generate_sortable`: the millisecond timestamp of the
+// last-generated sortable id, and its 10-byte random tail. Kept per-thread so callers
+// don't need to coordinate.
+thread_local! {
+    static LAST_SORTABLE: RefCell<(u64, [u8; 10])> = RefCell::new((0, [0u8; 10]));
+}
+
+// Increments a big-endian byte string by one, carrying into higher-order bytes.
+// Returns `true` if the increment overflowed (all bytes wrapped back to zero).
+fn increment_be(bytes: &mut [u8; 10]) -> bool {
+    for byte in bytes.iter_mut().rev() {
+        if *byte == u8::MAX {
+            *byte = 0;
+        } else {
+            *byte += 1;
+            return false;
+        }
+    }
+    true
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time is before the Unix epoch")
+        .as_millis() as u64
+}
+
 impl Id {
     pub fn get_from_buf(buf: &mut dyn bytes::Buf) -> Id {
         let mut arr = [0u8; 16];
@@ -20,10 +72,95 @@ impl Id {
         Id::from(arr)
     }
 
+    /// Symmetric counterpart to [`Id::get_from_buf`]: writes the 16 raw bytes to `buf`.
+    pub fn put_to_buf(&self, buf: &mut dyn bytes::BufMut) {
+        buf.put_slice(&self.0);
+    }
+
     pub fn as_arr(&self) -> [u8; 16] {
         self.0
     }
 
+    /// Encodes the id as its raw 16-byte binary form, e.g. for embedding in a binary
+    /// protocol or an on-disk record. Use [`Id::put_to_buf`] instead to write directly into
+    /// an existing buffer without the intermediate `Vec`.
+    pub fn encode(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+
+    /// Decodes an id previously produced by [`Id::encode`] or [`Id::put_to_buf`].
+    pub fn decode(bytes: &[u8]) -> Result<Id, IdDecodeError> {
+        let arr: [u8; 16] = bytes
+            .try_into()
+            .map_err(|_| IdDecodeError::WrongLength(bytes.len()))?;
+        Ok(Id(arr))
+    }
+
+    /// Generates a uniformly random id. Use [`Id::generate_sortable`] instead if the id
+    /// will be displayed or stored alongside others and creation order matters.
+    pub fn generate() -> Id {
+        let mut arr = [0u8; 16];
+        rand::thread_rng().fill(&mut arr);
+        Id(arr)
+    }
+
+    /// Generates a time-ordered id: the first 6 bytes are the big-endian Unix-millisecond
+    /// timestamp, and the remaining 10 bytes are random. Because `Ord` on `Id` compares the
+    /// underlying `[u8; 16]` byte-by-byte, ids generated later always sort after ids
+    /// generated earlier, without any coordination between callers.
+    ///
+    /// Ids generated within the same millisecond on the same thread get a monotonically
+    /// incremented tail instead of a fresh random one, so they still sort correctly relative
+    /// to each other. If the tail overflows within a millisecond, generation spills into the
+    /// next millisecond with a freshly randomized tail.
+    ///
+    /// If the wall clock ever goes backwards (NTP correction, VM migration, or simply the
+    /// overflow spill above having pushed `last_ms` ahead of real time), `last_ms` is reused
+    /// and the tail incremented instead, so ids keep sorting after everything generated so
+    /// far on this thread rather than regressing.
+    pub fn generate_sortable() -> Id {
+        LAST_SORTABLE.with(|state| {
+            let mut state = state.borrow_mut();
+            let (last_ms, last_tail) = &mut *state;
+
+            let mut ms = now_millis();
+            let tail = if ms <= *last_ms {
+                ms = *last_ms;
+                let mut tail = *last_tail;
+                if increment_be(&mut tail) {
+                    ms += 1;
+                    rand::thread_rng().fill(&mut tail);
+                }
+                tail
+            } else {
+                let mut tail = [0u8; 10];
+                rand::thread_rng().fill(&mut tail);
+                tail
+            };
+
+            *last_ms = ms;
+            *last_tail = tail;
+
+            let mut arr = [0u8; 16];
+            arr[..6].copy_from_slice(&ms.to_be_bytes()[2..]);
+            arr[6..].copy_from_slice(&tail);
+            Id(arr)
+        })
+    }
+
+    /// Encodes the id as 8 pronounceable proquint quintets separated by `-`, e.g.
+    /// `lusab-babad-gutih-tugad-lusab-babad-gutih-tugad`. Handy for reading ids aloud or
+    /// typing them during ops/debugging, where a hex string like
+    /// `ad50847381e248feaac9876cc71ae418` is error-prone.
+    pub fn to_proquint(&self) -> String {
+        proquint::encode(&self.0)
+    }
+
+    /// Parses an id previously produced by [`Id::to_proquint`].
+    pub fn from_proquint(s: &str) -> Result<Id, ProquintError> {
+        Ok(Id(proquint::decode(s)?))
+    }
+
     fn hex_encode(&self) -> String {
         static HEX: &[u8] = b"0123456789abcdef";
 
@@ -69,7 +206,7 @@ impl From<[u8; 16]> for Id {
 
 impl From<Id> for u128 {
     fn from(id: Id) -> Self {
-        u128::from_le_bytes(id.0)
+        u128::from_be_bytes(id.0)
     }
 }
 
@@ -92,13 +229,41 @@ macro_rules! id_newtype {
                 $t(Id::get_from_buf(buf))
             }
 
+            pub fn put_to_buf(&self, buf: &mut dyn bytes::BufMut) {
+                self.0.put_to_buf(buf)
+            }
+
             pub fn as_arr(&self) -> [u8; 16] {
                 self.0.as_arr()
             }
 
+            pub fn encode(&self) -> Vec<u8> {
+                self.0.encode()
+            }
+
+            pub fn decode(bytes: &[u8]) -> Result<$t, IdDecodeError> {
+                Ok($t(Id::decode(bytes)?))
+            }
+
             pub const fn from_array(b: [u8; 16]) -> Self {
                 $t(Id(b))
             }
+
+            pub fn generate() -> $t {
+                $t(Id::generate())
+            }
+
+            pub fn generate_sortable() -> $t {
+                $t(Id::generate_sortable())
+            }
+
+            pub fn to_proquint(&self) -> String {
+                self.0.to_proquint()
+            }
+
+            pub fn from_proquint(s: &str) -> Result<$t, ProquintError> {
+                Ok($t(Id::from_proquint(s)?))
+            }
         }
 
         impl FromStr for $t {
@@ -147,9 +312,186 @@ macro_rules! id_newtype {
                 self.0.fmt(f)
             }
         }
+
+        #[cfg(feature = "cbor")]
+        impl cbor::SerializeAs<$t> for cbor::IdAsBytes {
+            fn serialize_as<S: serde::Serializer>(
+                source: &$t,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                cbor::IdAsBytes::serialize_as(&source.0, serializer)
+            }
+        }
+
+        #[cfg(feature = "cbor")]
+        impl<'de> cbor::DeserializeAs<'de, $t> for cbor::IdAsBytes {
+            fn deserialize_as<D: serde::Deserializer<'de>>(deserializer: D) -> Result<$t, D::Error> {
+                Ok($t(cbor::IdAsBytes::deserialize_as(deserializer)?))
+            }
+        }
     };
 }
 
+/// Error returned by [`Id::decode`] when the input isn't exactly 16 bytes long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdDecodeError {
+    WrongLength(usize),
+}
+
+impl fmt::Display for IdDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdDecodeError::WrongLength(n) => write!(f, "expected 16 bytes, got {n}"),
+        }
+    }
+}
+
+impl std::error::Error for IdDecodeError {}
+
+/// Error returned by [`Id::from_proquint`] when the input isn't a valid proquint encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProquintError {
+    /// The string doesn't split into the 8 dash-separated quintets a 128-bit id needs.
+    WrongQuintetCount(usize),
+    /// A quintet wasn't exactly 5 characters long.
+    WrongQuintetLength(usize),
+    /// A character didn't belong to the expected consonant/vowel alphabet at its position.
+    InvalidChar(char),
+}
+
+impl fmt::Display for ProquintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProquintError::WrongQuintetCount(n) => {
+                write!(f, "expected 8 proquint quintets, got {n}")
+            }
+            ProquintError::WrongQuintetLength(n) => {
+                write!(
+                    f,
+                    "expected a 5-character proquint quintet, got {n} characters"
+                )
+            }
+            ProquintError::InvalidChar(c) => write!(f, "invalid proquint character: {c:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ProquintError {}
+
+/// `serde_with` adapter that (de)serializes an [`Id`] (and, via `id_newtype!`, `TenantId`
+/// and `TimelineId`) as a 16-byte binary string instead of the default array-of-integers
+/// encoding. With `ciborium` as the wire format, this comes out as a proper CBOR byte
+/// string (major type 2), not an array of 16 integers. Apply it with
+/// `#[serde_as(as = "IdAsBytes")]`; see [`Id`] for the hex-string alternative via
+/// `DisplayFromStr`.
+#[cfg(feature = "cbor")]
+mod cbor {
+    pub use serde_with::{DeserializeAs, SerializeAs};
+
+    use super::Id;
+
+    pub struct IdAsBytes;
+
+    impl SerializeAs<Id> for IdAsBytes {
+        fn serialize_as<S: serde::Serializer>(source: &Id, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(source.as_ref())
+        }
+    }
+
+    impl<'de> DeserializeAs<'de, Id> for IdAsBytes {
+        fn deserialize_as<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Id, D::Error> {
+            struct IdVisitor;
+
+            impl<'de> serde::de::Visitor<'de> for IdVisitor {
+                type Value = Id;
+
+                fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str("a 16-byte CBOR byte string")
+                }
+
+                fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Id, E> {
+                    Id::decode(v).map_err(E::custom)
+                }
+            }
+
+            deserializer.deserialize_bytes(IdVisitor)
+        }
+    }
+}
+
+/// Pronounceable proquint encoding ([A Proposal for Proquints](https://arxiv.org/html/0901.4016))
+/// of a 128-bit id as 8 dash-separated consonant-vowel-consonant-vowel-consonant quintets.
+mod proquint {
+    use super::ProquintError;
+
+    const CONSONANTS: [u8; 16] = *b"bdfghjklmnprstvz";
+    const VOWELS: [u8; 4] = *b"aiou";
+
+    fn consonant_index(c: u8) -> Option<u16> {
+        CONSONANTS.iter().position(|&x| x == c).map(|i| i as u16)
+    }
+
+    fn vowel_index(c: u8) -> Option<u16> {
+        VOWELS.iter().position(|&x| x == c).map(|i| i as u16)
+    }
+
+    fn encode_word(word: u16, out: &mut String) {
+        let c1 = (word >> 12) & 0xf;
+        let v1 = (word >> 10) & 0x3;
+        let c2 = (word >> 6) & 0xf;
+        let v2 = (word >> 4) & 0x3;
+        let c3 = word & 0xf;
+        out.push(CONSONANTS[c1 as usize] as char);
+        out.push(VOWELS[v1 as usize] as char);
+        out.push(CONSONANTS[c2 as usize] as char);
+        out.push(VOWELS[v2 as usize] as char);
+        out.push(CONSONANTS[c3 as usize] as char);
+    }
+
+    fn decode_word(quintet: &str) -> Result<u16, ProquintError> {
+        let bytes = quintet.as_bytes();
+        if bytes.len() != 5 {
+            return Err(ProquintError::WrongQuintetLength(bytes.len()));
+        }
+        let err = |c: u8| ProquintError::InvalidChar(c as char);
+        let c1 = consonant_index(bytes[0]).ok_or_else(|| err(bytes[0]))?;
+        let v1 = vowel_index(bytes[1]).ok_or_else(|| err(bytes[1]))?;
+        let c2 = consonant_index(bytes[2]).ok_or_else(|| err(bytes[2]))?;
+        let v2 = vowel_index(bytes[3]).ok_or_else(|| err(bytes[3]))?;
+        let c3 = consonant_index(bytes[4]).ok_or_else(|| err(bytes[4]))?;
+        Ok((c1 << 12) | (v1 << 10) | (c2 << 6) | (v2 << 4) | c3)
+    }
+
+    pub(super) fn encode(id: &[u8; 16]) -> String {
+        let mut out = String::with_capacity(16 * 5 / 2 + 7);
+        for (i, word) in id.chunks_exact(2).enumerate() {
+            if i > 0 {
+                out.push('-');
+            }
+            encode_word(u16::from_be_bytes([word[0], word[1]]), &mut out);
+        }
+        out
+    }
+
+    pub(super) fn decode(s: &str) -> Result<[u8; 16], ProquintError> {
+        let mut arr = [0u8; 16];
+        let mut count = 0;
+        for (i, quintet) in s.split('-').enumerate() {
+            if i >= 8 {
+                count = i + 1;
+                break;
+            }
+            let word = decode_word(quintet)?;
+            arr[i * 2..i * 2 + 2].copy_from_slice(&word.to_be_bytes());
+            count = i + 1;
+        }
+        if count != 8 {
+            return Err(ProquintError::WrongQuintetCount(count));
+        }
+        Ok(arr)
+    }
+}
+
 /// Neon timeline IDs are different from PostgreSQL timeline
 /// IDs. They serve a similar purpose though: they differentiate
 /// between different "histories" of the same cluster.  However,
@@ -281,3 +623,101 @@ mod poem {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_id() -> Id {
+        Id::generate()
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        let id = sample_id();
+        let parsed: Id = id.to_string().parse().expect("valid hex");
+        assert_eq!(id, parsed);
+    }
+
+    #[test]
+    fn proquint_round_trip() {
+        let id = sample_id();
+        let parsed = Id::from_proquint(&id.to_proquint()).expect("valid proquint");
+        assert_eq!(id, parsed);
+    }
+
+    #[test]
+    fn proquint_rejects_wrong_quintet_count() {
+        let id = sample_id();
+        let mut proquint = id.to_proquint();
+        proquint.push_str("-babab");
+        let err = Id::from_proquint(&proquint).unwrap_err();
+        assert_eq!(err, ProquintError::WrongQuintetCount(9));
+    }
+
+    #[test]
+    fn proquint_rejects_wrong_quintet_length() {
+        let id = sample_id();
+        let mut proquint = id.to_proquint();
+        proquint.truncate(proquint.len() - 1);
+        let err = Id::from_proquint(&proquint).unwrap_err();
+        assert_eq!(err, ProquintError::WrongQuintetLength(4));
+    }
+
+    #[test]
+    fn proquint_rejects_invalid_char() {
+        let id = sample_id();
+        let mut proquint = id.to_proquint();
+        proquint.replace_range(0..1, "q");
+        let err = Id::from_proquint(&proquint).unwrap_err();
+        assert_eq!(err, ProquintError::InvalidChar('q'));
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let id = sample_id();
+        let decoded = Id::decode(&id.encode()).expect("valid 16-byte encoding");
+        assert_eq!(id, decoded);
+
+        let mut buf = Vec::new();
+        id.put_to_buf(&mut buf);
+        let mut cursor = buf.as_slice();
+        let from_buf = Id::get_from_buf(&mut cursor);
+        assert_eq!(id, from_buf);
+    }
+
+    #[test]
+    fn decode_rejects_wrong_length() {
+        let err = Id::decode(&[0u8; 15]).unwrap_err();
+        assert_eq!(err, IdDecodeError::WrongLength(15));
+    }
+
+    #[test]
+    fn hex_and_binary_agree() {
+        let id = sample_id();
+        let from_hex: Id = id.to_string().parse().expect("valid hex");
+        let from_binary = Id::decode(&id.encode()).expect("valid 16-byte encoding");
+        assert_eq!(from_hex, from_binary);
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn cbor_round_trip() {
+        use serde_with::serde_as;
+
+        #[serde_as]
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Wrapper(#[serde_as(as = "IdAsBytes")] Id);
+
+        let id = sample_id();
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(&Wrapper(id), &mut bytes).expect("serializes");
+
+        // A CBOR byte string (major type 2) with a 16-byte payload starts with 0x50, not the
+        // 0x90-prefixed array-of-16-integers encoding the default derive would produce.
+        assert_eq!(bytes[0], 0x50);
+
+        let Wrapper(decoded) = ciborium::de::from_reader(bytes.as_slice()).expect("deserializes");
+        assert_eq!(id, decoded);
+    }
+}
@@ -0,0 +1,31 @@
+//! Protocol-version / capabilities handshake, shared by both server examples, so a client can
+//! discover which API version and optional features a build speaks over `GET /version`
+//! instead of guessing from trial and error.
+
+/// The semantic API version this build speaks, as `major.minor`. Bump the major component for
+/// breaking changes; clients pin to a major version via `Accept-Version`.
+pub const API_VERSION: &str = "1.0";
+
+/// The crate's own build version, handy for support/bug-report correlation -- distinct from
+/// [`API_VERSION`], which only moves when the wire protocol changes.
+pub const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Named, independently-toggleable pieces of API behavior a client can probe for instead of
+/// inferring them from [`API_VERSION`] alone.
+pub fn capabilities() -> Vec<&'static str> {
+    let mut caps = vec!["sortable-ids", "proquint-ids"];
+    #[cfg(feature = "cbor")]
+    caps.push("cbor");
+    caps
+}
+
+/// Checks whether `requested`, a client-supplied `major.minor` version (as sent via
+/// `Accept-Version`), is compatible with [`API_VERSION`]. Only the major component needs to
+/// match: a client asking for an older minor version still gets a superset of what it knows
+/// about, since minor bumps are additive.
+pub fn is_compatible(requested: &str) -> bool {
+    fn major(v: &str) -> Option<&str> {
+        v.split('.').next()
+    }
+    major(requested) == major(API_VERSION)
+}
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The JSON body every [`ApiError`] renders as, on both the poem and axum examples.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub struct ErrorBody {
+    pub msg: String,
+    pub detail: Option<String>,
+}
+
+/// A single error type shared by every endpoint, so that handlers can use `?` on leaf
+/// errors (e.g. `hex::FromHexError` from parsing an [`crate::Id`]) instead of hand-rolling a
+/// `match` over status codes per endpoint.
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("bad request: {0}")]
+    BadRequest(String),
+
+    #[error("not found: {0}")]
+    NotFound(String),
+
+    #[error("conflict: {0}")]
+    Conflict(String),
+
+    #[error("internal error")]
+    Internal(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl From<hex::FromHexError> for ApiError {
+    fn from(err: hex::FromHexError) -> Self {
+        ApiError::BadRequest(err.to_string())
+    }
+}
+
+impl From<crate::repository::RepositoryError> for ApiError {
+    fn from(err: crate::repository::RepositoryError) -> Self {
+        use crate::repository::RepositoryError;
+        match err {
+            RepositoryError::AlreadyExists(id) => {
+                ApiError::Conflict(format!("tenant {id} already exists"))
+            }
+            RepositoryError::NotFound(id) => ApiError::NotFound(format!("tenant {id} not found")),
+            RepositoryError::Backend(source) => ApiError::Internal(source),
+        }
+    }
+}
+
+impl ApiError {
+    fn body(&self) -> ErrorBody {
+        match self {
+            ApiError::BadRequest(msg) | ApiError::NotFound(msg) | ApiError::Conflict(msg) => {
+                ErrorBody {
+                    msg: msg.clone(),
+                    detail: None,
+                }
+            }
+            ApiError::Internal(source) => {
+                tracing::error!(error = %source, "internal error serving request");
+                ErrorBody {
+                    msg: "internal error".to_owned(),
+                    detail: None,
+                }
+            }
+        }
+    }
+}
+
+mod axum_impl {
+    use axum::{http::StatusCode, response::IntoResponse, Json};
+
+    use super::ApiError;
+
+    impl IntoResponse for ApiError {
+        fn into_response(self) -> axum::response::Response {
+            let status = match &self {
+                ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+                ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+                ApiError::Conflict(_) => StatusCode::CONFLICT,
+                ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, Json(self.body())).into_response()
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use std::{
+    cell::RefCell,
+    fmt,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use hex::FromHex;
+use rand::Rng;
+
+mod error;
+pub use error::{ApiError, ErrorBody};
+
+pub mod repository;
+
+pub mod version;
+
+/// 128-bit id shared by both server examples. Trimmed down to what this crate's axum/utoipa
+/// example actually uses -- see `try_poem::Id` for the proquint and binary encodings that
+/// example also offers.
+///
+/// NOTE: It (de)serializes as an array of hex bytes by default; use
+/// `#[serde_as(as = "DisplayFromStr")]` to (de)serialize it as a hex string instead, e.g.
+/// `ad50847381e248feaac9876cc71ae418`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Id([u8; 16]);
+
+// Thread-local state for `Id::generate_sortable`: the millisecond timestamp of the
+// last-generated sortable id, and its 10-byte random tail. Kept per-thread so callers don't
+// need to coordinate.
+thread_local! {
+    static LAST_SORTABLE: RefCell<(u64, [u8; 10])> = RefCell::new((0, [0u8; 10]));
+}
+
+// Increments a big-endian byte string by one, carrying into higher-order bytes. Returns
+// `true` if the increment overflowed (all bytes wrapped back to zero).
+fn increment_be(bytes: &mut [u8; 10]) -> bool {
+    for byte in bytes.iter_mut().rev() {
+        if *byte == u8::MAX {
+            *byte = 0;
+        } else {
+            *byte += 1;
+            return false;
+        }
+    }
+    true
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time is before the Unix epoch")
+        .as_millis() as u64
+}
+
+impl Id {
+    pub fn as_arr(&self) -> [u8; 16] {
+        self.0
+    }
+
+    pub const fn from_array(b: [u8; 16]) -> Self {
+        Id(b)
+    }
+
+    /// Generates a uniformly random id. Use [`Id::generate_sortable`] instead if the id will
+    /// be displayed or stored alongside others and creation order matters.
+    pub fn generate() -> Id {
+        let mut arr = [0u8; 16];
+        rand::thread_rng().fill(&mut arr);
+        Id(arr)
+    }
+
+    /// Generates a time-ordered id: the first 6 bytes are the big-endian Unix-millisecond
+    /// timestamp, and the remaining 10 bytes are random. Because `Ord` on `Id` compares the
+    /// underlying `[u8; 16]` byte-by-byte, ids generated later always sort after ids
+    /// generated earlier, without any coordination between callers.
+    ///
+    /// Ids generated within the same millisecond on the same thread get a monotonically
+    /// incremented tail instead of a fresh random one, so they still sort correctly relative
+    /// to each other. If the tail overflows within a millisecond, generation spills into the
+    /// next millisecond with a freshly randomized tail. If the wall clock ever goes
+    /// backwards, `last_ms` is reused and the tail incremented instead, so ids keep sorting
+    /// after everything generated so far on this thread rather than regressing.
+    pub fn generate_sortable() -> Id {
+        LAST_SORTABLE.with(|state| {
+            let mut state = state.borrow_mut();
+            let (last_ms, last_tail) = &mut *state;
+
+            let mut ms = now_millis();
+            let tail = if ms <= *last_ms {
+                ms = *last_ms;
+                let mut tail = *last_tail;
+                if increment_be(&mut tail) {
+                    ms += 1;
+                    rand::thread_rng().fill(&mut tail);
+                }
+                tail
+            } else {
+                let mut tail = [0u8; 10];
+                rand::thread_rng().fill(&mut tail);
+                tail
+            };
+
+            *last_ms = ms;
+            *last_tail = tail;
+
+            let mut arr = [0u8; 16];
+            arr[..6].copy_from_slice(&ms.to_be_bytes()[2..]);
+            arr[6..].copy_from_slice(&tail);
+            Id(arr)
+        })
+    }
+
+    fn hex_encode(&self) -> String {
+        static HEX: &[u8] = b"0123456789abcdef";
+
+        let mut buf = vec![0u8; self.0.len() * 2];
+        for (&b, chunk) in self.0.as_ref().iter().zip(buf.chunks_exact_mut(2)) {
+            chunk[0] = HEX[((b >> 4) & 0xf) as usize];
+            chunk[1] = HEX[(b & 0xf) as usize];
+        }
+        unsafe { String::from_utf8_unchecked(buf) }
+    }
+}
+
+impl FromStr for Id {
+    type Err = hex::FromHexError;
+
+    fn from_str(s: &str) -> Result<Id, Self::Err> {
+        Self::from_hex(s)
+    }
+}
+
+// this is needed for pretty serialization and deserialization of Id's using serde integration with hex crate
+impl FromHex for Id {
+    type Error = hex::FromHexError;
+
+    fn from_hex<T: AsRef<[u8]>>(hex: T) -> Result<Self, Self::Error> {
+        let mut buf: [u8; 16] = [0u8; 16];
+        hex::decode_to_slice(hex, &mut buf)?;
+        Ok(Id(buf))
+    }
+}
+
+impl AsRef<[u8]> for Id {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<[u8; 16]> for Id {
+    fn from(b: [u8; 16]) -> Self {
+        Id(b)
+    }
+}
+
+impl From<Id> for u128 {
+    fn from(id: Id) -> Self {
+        u128::from_be_bytes(id.0)
+    }
+}
+
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.hex_encode())
+    }
+}
+
+impl fmt::Debug for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.hex_encode())
+    }
+}
+
+macro_rules! id_newtype {
+    ($t:ident) => {
+        impl $t {
+            pub fn as_arr(&self) -> [u8; 16] {
+                self.0.as_arr()
+            }
+
+            pub const fn from_array(b: [u8; 16]) -> Self {
+                $t(Id(b))
+            }
+
+            pub fn generate() -> $t {
+                $t(Id::generate())
+            }
+
+            pub fn generate_sortable() -> $t {
+                $t(Id::generate_sortable())
+            }
+        }
+
+        impl FromStr for $t {
+            type Err = hex::FromHexError;
+
+            fn from_str(s: &str) -> Result<$t, Self::Err> {
+                let value = Id::from_str(s)?;
+                Ok($t(value))
+            }
+        }
+
+        impl From<[u8; 16]> for $t {
+            fn from(b: [u8; 16]) -> Self {
+                $t(Id::from(b))
+            }
+        }
+
+        impl From<$t> for u128 {
+            fn from(id: $t) -> Self {
+                u128::from(id.0)
+            }
+        }
+
+        impl fmt::Display for $t {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl fmt::Debug for $t {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+    };
+}
+
+/// Identifies a particular tenant; distinguishes requests and data belonging to different
+/// users. See `try_poem::TenantId` for the poem_openapi-integrated counterpart.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TenantId(Id);
+
+id_newtype!(TenantId);
+
+mod utoipa_impl {
+    use utoipa::openapi::{ObjectBuilder, RefOr, Schema, SchemaFormat, SchemaType};
+
+    use super::{Id, TenantId};
+
+    fn hex_id_schema(name: &'static str) -> (&'static str, RefOr<Schema>) {
+        (
+            name,
+            ObjectBuilder::new()
+                .schema_type(SchemaType::String)
+                .format(Some(SchemaFormat::Custom("hex".to_owned())))
+                .description(Some("A 128-bit id, rendered as a hex string"))
+                .into(),
+        )
+    }
+
+    impl<'s> utoipa::ToSchema<'s> for Id {
+        fn schema() -> (&'s str, RefOr<Schema>) {
+            hex_id_schema("Id")
+        }
+    }
+
+    impl<'s> utoipa::ToSchema<'s> for TenantId {
+        fn schema() -> (&'s str, RefOr<Schema>) {
+            hex_id_schema("TenantId")
+        }
+    }
+}